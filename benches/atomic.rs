@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate criterion;
+#[macro_use]
+extern crate simple_counter;
+
+use criterion::{black_box, Criterion};
+
+generate_atomic_counter!(SeqCstCounter, usize);
+generate_atomic_counter!(RelaxedCounter, usize, ordering = Relaxed);
+
+fn bench_ordering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("atomic");
+
+    group.bench_function("SeqCst", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                black_box(SeqCstCounter::next());
+            }
+        })
+    });
+
+    group.bench_function("Relaxed", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                black_box(RelaxedCounter::next());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ordering);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate criterion;
+#[macro_use]
+extern crate simple_counter;
+
+use criterion::{black_box, Criterion};
+
+generate_counter!(Counter, usize);
+generate_counter!(Outlined, usize);
+
+// The same generated `next()`, but kept behind a call that is never inlined.
+#[inline(never)]
+fn outlined_next() -> usize {
+    Outlined::next()
+}
+
+fn bench_next(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next");
+
+    group.bench_function("inline", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                black_box(Counter::next());
+            }
+        })
+    });
+
+    group.bench_function("inline(never)", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                black_box(outlined_next());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_next);
+criterion_main!(benches);
@@ -0,0 +1,214 @@
+/// Generates a global counter shared across all threads.
+///
+/// Unlike `generate_counter!`, which gives each thread its own independent
+/// sequence, the counter generated here is backed by a static atomic integer
+/// and is safe to use from any number of threads at once. Every call to
+/// `next()` hands out a value that no other thread will see, which makes it
+/// suitable for globally unique IDs. As with the atomic types themselves,
+/// the counter silently wraps around on overflow.
+///
+/// `compare_and_set(current, new)` maps onto `compare_exchange`, storing
+/// `new` only if the counter still holds `current`, and `fetch_reset()`
+/// reads and zeroes the counter in a single `swap`, so no increments from
+/// other threads are lost in between.
+///
+/// Only primitive integer types with a matching `std::sync::atomic` type are
+/// supported.
+///
+/// Every operation uses `SeqCst` ordering by default. If the counter is only
+/// used to hand out unique values, rather than to order other memory accesses
+/// between threads, `ordering = Relaxed` is just as unique and avoids the
+/// extra fences `SeqCst` needs on weakly-ordered architectures such as ARM.
+/// `Relaxed` and `SeqCst` are the only orderings accepted.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// use std::thread;
+/// 
+/// generate_atomic_counter!(Counter, usize);
+/// 
+/// fn main() {
+/// 
+///   let handle = thread::spawn(|| Counter::next());
+///   let mine = Counter::next();
+///   let theirs = handle.join().unwrap();
+/// 
+///   assert_ne!(mine, theirs);
+///   assert_eq!(Counter::next(), 2);
+/// 
+///   Counter::reset();
+/// 
+///   assert_eq!(Counter::next(), 0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_atomic_counter {
+    ($name:ident, $type:ident) => {
+        $crate::generate_atomic_counter!($name, $type, ordering = SeqCst);
+    };
+
+    ($name:ident, $type:ident, ordering = $ordering:ident) => {
+
+        /// Generated by the `simple-counter` crate.
+        #[allow(non_snake_case)]
+        pub mod $name {
+            use $crate::__private::atomic::Ordering;
+
+            type Atomic = $crate::__atomic_type!($type);
+
+            const ORDERING: Ordering = $crate::__atomic_ordering!($ordering);
+
+            static COUNTER: Atomic = Atomic::new(0);
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn next() -> $type {
+                COUNTER.fetch_add(1, ORDERING)
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn peek() -> $type {
+                COUNTER.load(ORDERING)
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn set(n: $type) {
+                COUNTER.store(n, ORDERING);
+            }
+
+            #[allow(dead_code)]
+            pub fn compare_and_set(current: $type, new: $type) -> bool {
+                COUNTER
+                    .compare_exchange(current, new, ORDERING, ORDERING)
+                    .is_ok()
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn reset() {
+                COUNTER.store(0, ORDERING);
+            }
+
+            #[allow(dead_code)]
+            pub fn fetch_reset() -> $type {
+                COUNTER.swap(0, ORDERING)
+            }
+        }
+    };
+}
+
+/// Restricts the orderings to those valid for every atomic operation used.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __atomic_ordering {
+    (Relaxed) => { $crate::__private::atomic::Ordering::Relaxed };
+    (SeqCst) => { $crate::__private::atomic::Ordering::SeqCst };
+    ($other:ident) => {
+        compile_error!(concat!("unsupported counter ordering `", stringify!($other), "`"))
+    };
+}
+
+/// Maps a primitive integer type to its `core::sync::atomic` counterpart.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __atomic_type {
+    (u8) => { $crate::__private::atomic::AtomicU8 };
+    (u16) => { $crate::__private::atomic::AtomicU16 };
+    (u32) => { $crate::__private::atomic::AtomicU32 };
+    (u64) => { $crate::__private::atomic::AtomicU64 };
+    (usize) => { $crate::__private::atomic::AtomicUsize };
+    (i8) => { $crate::__private::atomic::AtomicI8 };
+    (i16) => { $crate::__private::atomic::AtomicI16 };
+    (i32) => { $crate::__private::atomic::AtomicI32 };
+    (i64) => { $crate::__private::atomic::AtomicI64 };
+    (isize) => { $crate::__private::atomic::AtomicIsize };
+    ($other:ident) => {
+        compile_error!(concat!("no atomic type for `", stringify!($other), "`"))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashSet;
+    use std::thread;
+
+    #[test]
+    fn test_basic() {
+        generate_atomic_counter!(Counter, u32);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::peek());
+        assert_eq!(2, Counter::next());
+    }
+
+    #[test]
+    fn test_set_reset() {
+        generate_atomic_counter!(Counter, i64);
+        Counter::set(-10);
+        assert_eq!(-10, Counter::next());
+        assert_eq!(-9, Counter::next());
+        Counter::reset();
+        assert_eq!(0, Counter::next());
+    }
+
+    #[test]
+    fn test_compare_and_set() {
+        generate_atomic_counter!(Counter, u8);
+        Counter::set(5);
+        assert!(!Counter::compare_and_set(4, 10));
+        assert_eq!(5, Counter::peek());
+        assert!(Counter::compare_and_set(5, 10));
+        assert_eq!(10, Counter::next());
+    }
+
+    #[test]
+    fn test_fetch_reset() {
+        generate_atomic_counter!(Counter, usize);
+        for _ in 0..5 {
+            Counter::next();
+        }
+        assert_eq!(5, Counter::fetch_reset());
+        assert_eq!(0, Counter::peek());
+        assert_eq!(0, Counter::next());
+    }
+
+    #[test]
+    fn test_threads() {
+        generate_atomic_counter!(Counter, usize);
+
+        let handles = (0..8)
+            .map(|_| thread::spawn(|| {
+                (0..1000).map(|_| Counter::next()).collect::<Vec<_>>()
+            }))
+            .collect::<Vec<_>>();
+
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for n in handle.join().unwrap() {
+                assert!(seen.insert(n));
+            }
+        }
+
+        assert_eq!(8000, seen.len());
+        assert_eq!(8000, Counter::peek());
+    }
+
+    #[test]
+    fn test_relaxed() {
+        generate_atomic_counter!(Counter, u64, ordering = Relaxed);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        Counter::set(10);
+        assert!(Counter::compare_and_set(10, 20));
+        assert_eq!(20, Counter::next());
+        assert_eq!(21, Counter::fetch_reset());
+        assert_eq!(0, Counter::next());
+    }
+}
@@ -0,0 +1,124 @@
+/// Generates a counter type that can be constructed in `const` contexts.
+///
+/// The thread-local `Cell` behind `generate_counter!` can't be created or
+/// read at compile time. This instead generates a `struct` wrapping the
+/// matching atomic integer, with the starting value as an associated
+/// `START` constant, so counters can live in plain `static` items and be
+/// shared across threads.
+///
+/// Only `START` and `new()` are usable in `const` contexts, since they never
+/// touch the atomic. Everything that reads or advances the counter, namely
+/// `next()`, `peek()`, `set()`, and `reset()`, happens at runtime.
+///
+/// Like `generate_atomic_counter!`, only primitive integer types with a
+/// matching atomic type are supported.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_const_counter!(Counter, usize, start = 100);
+/// 
+/// static REQUESTS: Counter = Counter::new();
+/// const FIRST: usize = Counter::START;
+/// 
+/// fn main() {
+/// 
+///   assert_eq!(REQUESTS.next(), FIRST);
+///   assert_eq!(REQUESTS.next(), 101);
+/// 
+///   REQUESTS.reset();
+/// 
+///   assert_eq!(REQUESTS.next(), 100);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_const_counter {
+    ($(#[$attr:meta])* $name:ident, $($rest:tt)*) => {
+        $crate::generate_const_counter!($(#[$attr])* pub $name, $($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, $type:ident) => {
+        $crate::generate_const_counter!($(#[$attr])* $vis $name, $type, start = 0);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, $type:ident, start = $start:expr) => {
+
+        $(#[$attr])*
+        /// Generated by the `simple-counter` crate.
+        #[derive(Debug)]
+        $vis struct $name {
+            value: $crate::__atomic_type!($type),
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            pub const START: $type = $start;
+
+            pub const fn new() -> Self {
+                $name {
+                    value: <$crate::__atomic_type!($type)>::new(Self::START),
+                }
+            }
+
+            #[inline]
+            pub fn next(&self) -> $type {
+                self.value.fetch_add(1, $crate::__private::atomic::Ordering::SeqCst)
+            }
+
+            #[inline]
+            pub fn peek(&self) -> $type {
+                self.value.load($crate::__private::atomic::Ordering::SeqCst)
+            }
+
+            #[inline]
+            pub fn set(&self, n: $type) {
+                self.value.store(n, $crate::__private::atomic::Ordering::SeqCst);
+            }
+
+            #[inline]
+            pub fn reset(&self) {
+                self.set(Self::START);
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::thread;
+
+    #[test]
+    fn test_const() {
+        generate_const_counter!(Counter, u32, start = 7);
+
+        const START: u32 = Counter::START;
+        const _: () = assert!(START == 7);
+        static SHARED: Counter = Counter::new();
+
+        assert_eq!(7, SHARED.next());
+        thread::spawn(|| SHARED.next()).join().unwrap();
+        assert_eq!(9, SHARED.next());
+        SHARED.reset();
+        assert_eq!(7, SHARED.peek());
+    }
+
+    #[test]
+    fn test_default() {
+        generate_const_counter!(Counter, i64);
+        let counter = Counter::default();
+        assert_eq!(0, Counter::START);
+        counter.set(-3);
+        assert_eq!(-3, counter.next());
+        assert_eq!(-2, counter.next());
+    }
+}
@@ -0,0 +1,244 @@
+use number::Number;
+use state::State;
+
+/// The functions behind every counter generated by `generate_counter!`.
+///
+/// Each generated module implements this for a private type, overriding
+/// items according to its options, and forwards its public functions to the
+/// provided methods. Keeping the logic generic means the counter type is only
+/// named in that one impl, so a type that isn't `Number` fails there with a
+/// single error instead of in every generated function.
+#[doc(hidden)]
+pub trait Counter: 'static {
+    type Value: Number;
+
+    const START: Self::Value = <Self::Value as Number>::ZERO;
+    const STEP: Self::Value = <Self::Value as Number>::ONE;
+    const MIN: Self::Value = <Self::Value as Number>::MIN;
+    const MAX: Self::Value = <Self::Value as Number>::MAX;
+    const MONOTONIC: bool = false;
+
+    /// Runs `f` with the counter's state, like `LocalKey::with`.
+    fn with<R, F: FnOnce(&State<Self::Value>) -> R>(f: F) -> R;
+
+    fn reduce(n: Self::Value) -> Self::Value {
+        n
+    }
+
+    fn retreat(n: Self::Value) -> Self::Value {
+        n - Self::STEP
+    }
+
+    fn record(_: Self::Value) {}
+
+    fn register() {}
+
+    // Every write goes through here so options can observe it.
+    #[inline]
+    fn store(state: &State<Self::Value>, n: Self::Value) {
+        state.value.set(n);
+        Self::record(n);
+    }
+
+    // Every value handed out goes through here so it is counted and the
+    // callback sees it.
+    #[inline]
+    fn issue(state: &State<Self::Value>, n: Self::Value) -> Self::Value {
+        state.total.set(state.total.get() + 1);
+        if let Some(callback) = state.callback.get() {
+            callback(n);
+        }
+        n
+    }
+
+    /// Stores `n` without the `monotonic` check, for putting the counter back.
+    /// Takes a reference so that guards can call it from `drop`.
+    fn overwrite(n: &Self::Value) {
+        Self::with(|state| Self::store(state, *n));
+    }
+
+    fn on_next(callback: fn(Self::Value)) {
+        Self::with(|state| state.callback.set(Some(callback)));
+    }
+
+    fn clear_on_next() {
+        Self::with(|state| state.callback.set(None));
+    }
+
+    fn total_issued() -> usize {
+        Self::with(|state| state.total.get())
+    }
+
+    #[inline]
+    fn next() -> Self::Value {
+        Self::with(|state| {
+            let n = state.value.get();
+            Self::store(state, Self::reduce(n + Self::STEP));
+            Self::issue(state, Self::reduce(n))
+        })
+    }
+
+    #[inline]
+    fn add(by: Self::Value) -> Self::Value {
+        Self::with(|state| {
+            let n = state.value.get();
+            Self::store(state, Self::reduce(n + by));
+            Self::reduce(n)
+        })
+    }
+
+    fn skip(n: Self::Value) {
+        Self::with(|state| Self::store(state, Self::reduce(state.value.get() + n)));
+    }
+
+    fn next_value() -> Self::Value {
+        Self::with(|state| {
+            let n = Self::reduce(state.value.get() + Self::STEP);
+            Self::store(state, n);
+            Self::issue(state, n)
+        })
+    }
+
+    fn checked_next() -> Option<Self::Value> {
+        Self::with(|state| {
+            let n = state.value.get();
+            Number::checked_add(n, Self::STEP).map(|m| {
+                Self::store(state, Self::reduce(m));
+                Self::issue(state, Self::reduce(n))
+            })
+        })
+    }
+
+    fn wrapping_next() -> Self::Value {
+        Self::with(|state| {
+            let n = state.value.get();
+            Self::store(state, Self::reduce(Number::wrapping_add(n, Self::STEP)));
+            Self::issue(state, Self::reduce(n))
+        })
+    }
+
+    fn saturating_next() -> Self::Value {
+        Self::with(|state| {
+            let n = state.value.get();
+            Self::store(state, Self::reduce(Number::saturating_add(n, Self::STEP)));
+            Self::issue(state, Self::reduce(n))
+        })
+    }
+
+    fn prev() -> Self::Value {
+        Self::with(|state| {
+            let n = Self::retreat(state.value.get());
+            Self::store(state, n);
+            n
+        })
+    }
+
+    #[inline]
+    fn peek() -> Self::Value {
+        Self::with(|state| Self::reduce(state.value.get()))
+    }
+
+    fn get() -> Self::Value {
+        Self::with(|state| state.value.get())
+    }
+
+    #[inline]
+    fn set(n: Self::Value) {
+        Self::with(|state| {
+            debug_assert!(
+                !Self::MONOTONIC || n >= state.value.get(),
+                "monotonic counter cannot be set backwards",
+            );
+            Self::store(state, n)
+        });
+    }
+
+    fn compare_and_set(current: Self::Value, new: Self::Value) -> bool {
+        Self::with(|state| {
+            let swap = state.value.get() == current;
+            if swap {
+                Self::store(state, new);
+            }
+            swap
+        })
+    }
+
+    fn update<F: FnOnce(Self::Value) -> Self::Value>(f: F) -> Self::Value {
+        Self::with(|state| {
+            let n = f(state.value.get());
+            Self::store(state, n);
+            n
+        })
+    }
+
+    #[inline]
+    fn reset() {
+        Self::with(|state| Self::store(state, Self::START));
+    }
+
+    fn fetch_reset() -> Self::Value {
+        Self::with(|state| {
+            let n = state.value.get();
+            Self::store(state, Self::START);
+            n
+        })
+    }
+
+    /// Like `next()`, but only while the counter is below `ceiling`.
+    fn try_next(ceiling: Self::Value) -> Option<Self::Value> {
+        Self::with(|state| {
+            let n = state.value.get();
+            if n < ceiling {
+                // Near the maximum of the type, stepping past the ceiling
+                // could overflow, so stop at the ceiling instead.
+                let next = Number::checked_add(n, Self::STEP).unwrap_or(ceiling);
+                Self::store(state, Self::reduce(next));
+                Some(Self::issue(state, Self::reduce(n)))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// How many more values `try_next(ceiling)` will issue.
+    fn remaining(ceiling: Self::Value) -> Self::Value {
+        let n = Self::get();
+        if n < ceiling {
+            Number::div_ceil(ceiling - n, Self::STEP)
+        } else {
+            <Self::Value as Number>::ZERO
+        }
+    }
+
+    // The `count` values starting from `n`, and the value after them.
+    #[cfg(feature = "std")]
+    fn upcoming(mut n: Self::Value, count: usize) -> (Vec<Self::Value>, Self::Value) {
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            values.push(Self::reduce(n));
+            n = Self::reduce(n + Self::STEP);
+        }
+        (values, n)
+    }
+
+    #[cfg(feature = "std")]
+    fn next_n(count: usize) -> Vec<Self::Value> {
+        Self::with(|state| {
+            let (values, n) = Self::upcoming(state.value.get(), count);
+            Self::store(state, n);
+            // Updated once for the whole batch rather than per value.
+            state.total.set(state.total.get() + count);
+            if let Some(callback) = state.callback.get() {
+                for &n in &values {
+                    callback(n);
+                }
+            }
+            values
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn peek_n(count: usize) -> Vec<Self::Value> {
+        Self::upcoming(Self::get(), count).0
+    }
+}
@@ -0,0 +1,15 @@
+use core::fmt;
+
+/// Returned by `try_reset()` once a counter generated with `max_resets` has
+/// used up all of its resets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResetLimitExceeded;
+
+impl fmt::Display for ResetLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "counter reset limit exceeded")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for ResetLimitExceeded {}
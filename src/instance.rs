@@ -0,0 +1,182 @@
+/// Generates a counter type that can be instantiated any number of times.
+///
+/// Where `generate_counter!` generates a single global counter, this
+/// generates a `struct` wrapping a `Cell`, so independent counters can be
+/// created at runtime, stored in collections, and moved around like any
+/// other value. Like `generate_counter!`, the struct is `pub` unless a
+/// visibility is given before the name, and any attributes are forwarded.
+///
+/// Counters can be advanced with `+=` and compare equal when they hold the
+/// same current value.
+///
+/// With the `serde` feature enabled, the struct also implements `Serialize`
+/// and `Deserialize`, represented as just its current value.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_counter_type!(Counter, usize);
+/// 
+/// fn main() {
+/// 
+///   let a = Counter::new();
+///   let b = Counter::new();
+/// 
+///   assert_eq!(a.next(), 0);
+///   assert_eq!(a.next(), 1);
+///   assert_eq!(b.next(), 0);
+/// 
+///   a.reset();
+/// 
+///   assert_eq!(a.next(), 0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_counter_type {
+    ($(#[$attr:meta])* $name:ident, $($rest:tt)*) => {
+        $crate::generate_counter_type!($(#[$attr])* pub $name, $($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, $type:ty) => {
+
+        $(#[$attr])*
+        /// Generated by the `simple-counter` crate.
+        #[derive(Clone, Debug, Default, PartialEq)]
+        $vis struct $name {
+            value: $crate::__private::Cell<$type>,
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            pub const fn new() -> Self {
+                $name {
+                    value: $crate::__private::Cell::new(0 as $type),
+                }
+            }
+
+            #[inline]
+            pub fn next(&self) -> $type {
+                let n = self.value.get();
+                self.value.set(n + 1 as $type);
+                n
+            }
+
+            #[inline]
+            pub fn peek(&self) -> $type {
+                self.value.get()
+            }
+
+            #[inline]
+            pub fn set(&self, n: $type) {
+                self.value.set(n);
+            }
+
+            #[inline]
+            pub fn reset(&self) {
+                self.value.set(0 as $type);
+            }
+        }
+
+        impl $crate::__private::ops::AddAssign<$type> for $name {
+            #[inline]
+            fn add_assign(&mut self, n: $type) {
+                self.value.set(self.value.get() + n);
+            }
+        }
+
+        $crate::__counter_type_serde!($name, $type);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __counter_type_serde {
+    ($name:ident, $type:ty) => {
+        impl $crate::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serde::Serialize::serialize(&self.value.get(), serializer)
+            }
+        }
+
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                <$type as $crate::__private::serde::Deserialize>::deserialize(deserializer)
+                    .map(|n| $name { value: $crate::__private::Cell::new(n) })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __counter_type_serde {
+    ($name:ident, $type:ty) => {};
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_independent() {
+        generate_counter_type!(Counter, u32);
+        let a = Counter::new();
+        let b = Counter::new();
+        assert_eq!(0, a.next());
+        assert_eq!(1, a.next());
+        assert_eq!(0, b.next());
+        assert_eq!(2, a.peek());
+        assert_eq!(1, b.peek());
+    }
+
+    #[test]
+    fn test_set_reset() {
+        generate_counter_type!(Counter, i64);
+        let counters = (0..2).map(|_| Counter::default()).collect::<Vec<_>>();
+        counters[0].set(-5);
+        assert_eq!(-5, counters[0].next());
+        assert_eq!(0, counters[1].next());
+        counters[0].reset();
+        assert_eq!(0, counters[0].next());
+        assert_eq!(1, counters[1].next());
+    }
+
+    #[test]
+    fn test_operators() {
+        generate_counter_type!(Counter, usize);
+        let mut a = Counter::new();
+        let b = Counter::new();
+        assert_eq!(a, b);
+        a += 5;
+        assert_ne!(a, b);
+        assert_eq!(5, a.next());
+        b.set(6);
+        assert_eq!(a, b);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        extern crate serde_json;
+
+        generate_counter_type!(Counter, u64);
+        let counter = Counter::new();
+        counter.set(42);
+        let json = serde_json::to_string(&counter).unwrap();
+        assert_eq!("42", json);
+        let restored = serde_json::from_str::<Counter>(&json).unwrap();
+        assert_eq!(42, restored.next());
+        assert_eq!(43, restored.next());
+        assert_eq!(42, counter.next());
+    }
+}
@@ -1,5 +1,172 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(any(feature = "std", test))]
+extern crate core;
+
+#[macro_use]
+mod atomic;
+mod error;
+#[macro_use]
+mod constant;
+mod counter;
+#[macro_use]
+mod instance;
+#[macro_use]
+mod no_std;
+mod number;
+#[macro_use]
+mod pair;
+#[cfg(feature = "std")]
+#[macro_use]
+pub mod registry;
+mod state;
+#[cfg(feature = "std")]
+#[macro_use]
+mod sync;
+#[macro_use]
+mod unique;
+
+pub use error::ResetLimitExceeded;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use core::cell::Cell;
+    pub use core::marker::PhantomData;
+    pub use core::ops;
+    pub use core::sync::atomic;
+    pub use counter::Counter;
+    pub use no_std::SingleThreaded;
+    pub use number::Number;
+    pub use state::State;
+
+    #[cfg(feature = "std")]
+    pub use std::sync::{LazyLock, Mutex, MutexGuard, Once};
+
+    #[cfg(feature = "serde")]
+    pub extern crate serde;
+}
+
 /// Generates a thread-local global counter.
 ///
+/// Counters can be generated for any primitive integer or floating-point
+/// type, or an alias of one; anything else is rejected at compile time with
+/// the error "counter type must be numeric". The type and the values of any
+/// options can refer to items in the calling module. The bounds of the type
+/// are available as `MIN` and `MAX`. Counters start at `0` by default. A
+/// different starting value can be passed with `start = <expr>`, in which
+/// case `reset()` also returns to it.
+/// Similarly, `step = <expr>` changes how far each call to `next()` advances
+/// the counter.
+///
+/// `reset_to(n)` behaves like `set(n)`, restarting the counter from `n`
+/// without changing where later calls to `reset()` return to. To read the
+/// counter and reset it in one step, e.g. when periodically flushing metrics,
+/// use `fetch_reset()`, which returns the value from just before the reset.
+///
+/// `next()` returns the current value and then advances the counter, whereas
+/// `next_value()` advances first and returns the new value, so a fresh
+/// counter yields `1, 2, 3` from the latter. In the other direction, `prev()`
+/// steps the counter back and returns the decremented value, so a `prev()`
+/// immediately after a `next()` returns the same value again. All three use
+/// ordinary arithmetic: signed counters can freely go negative, and
+/// overflowing the type (including decrementing an unsigned counter past
+/// zero) panics in debug builds and wraps in release builds.
+///
+/// `next_into(&mut out)` is the same as `next()`, but writes the value into
+/// `out` instead of returning it, so a single slot can be reused across
+/// calls in a tight loop.
+///
+/// To detect when a counter runs out of values instead, use `checked_next()`,
+/// which returns `None` and leaves the counter untouched if advancing it
+/// would overflow. For silent wraparound on overflow in every build, e.g. when
+/// generating ring buffer indices, use `wrapping_next()`. To stop at the
+/// maximum value of the type instead, use `saturating_next()`, which keeps
+/// returning the maximum once it has been reached. Floating-point counters
+/// have no overflow in the integer sense, so for them `checked_next()` fails
+/// once the counter would become infinite, `saturating_next()` stops at the
+/// largest finite value, and `wrapping_next()` is the same as `next()`.
+///
+/// Passing `floor = <expr>` stops `prev()` from going below the floor: once
+/// reached, it keeps returning the floor instead of underflowing.
+///
+/// Passing `modulo = <expr>` makes the counter cycle through the
+/// values `0` up to (but not including) the modulus, handy for round-robin
+/// selection. A modulus of zero is rejected at compile time. The modulus
+/// only applies to values handed out by `next()` and friends; `prev()` does
+/// not cycle backwards past zero. `wrap = <expr>` is accepted as an alias.
+///
+/// To combine several options, list them in braces instead, in any order and
+/// written as `key: value`, or just `key` for flags such as `monotonic`.
+/// `generate_counter!(Counter, usize, { start: 100, step: 2, wrap: 200 })`
+/// counts up from `100` in twos and cycles back to `0` upon reaching `200`.
+///
+/// There are two ways to inspect a counter without changing it. `peek()`
+/// returns the value the next call to `next()` will hand out, while `get()`
+/// returns the stored value itself, which reads better when the counter is
+/// used as an accumulator via `add()` and `set()`. The two only differ for
+/// counters with a modulus, where `peek()` is reduced by it and `get()` is not.
+///
+/// To reserve a block of values at once, `add(n)` returns the current value
+/// like `next()` but advances the counter by `n` instead of the step, while
+/// `next_n(count)` collects the next `count` values into a `Vec`. To simply
+/// discard values, `skip(n)` fast-forwards the counter by `n`. `peek_n(count)`
+/// returns the same values as `next_n(count)` without advancing the counter.
+/// Both are only available with `std`.
+///
+/// For instrumentation, `on_next(f)` registers a function that is called
+/// with every value handed out by `next()` and its variants, until it is
+/// replaced by another call to `on_next()` or removed with `clear_on_next()`.
+/// Separately, `total_issued()` counts how many values have been handed out
+/// that way over the life of the counter, which `set()`, `reset()` and the
+/// like leave untouched.
+///
+/// Code that needs to put the counter back the way it found it, such as a
+/// test fixture, can save its state with `snapshot()` and later hand it to
+/// `restore()`. Alternatively, `scope()` returns a guard that does the same
+/// automatically when it goes out of scope, and `with_counter(start, f)`
+/// runs `f` with the counter set to `start`, restoring it afterwards even if
+/// `f` panics.
+///
+/// For transitions other than adding to the counter, `update(f)` replaces
+/// the stored value with the result of applying `f` to it and returns the
+/// new value. Guarded updates can use `compare_and_set(current, new)`, which
+/// only stores `new` if the counter holds `current` and reports whether it
+/// did.
+///
+/// Passing `max = <expr>` caps the values a counter can issue through
+/// `try_next()`, which returns `None` without advancing once the counter
+/// reaches the ceiling. `remaining()` reports how many values it has left to
+/// issue, taking the step into account. `next()` itself is unaffected.
+///
+/// Passing `max_resets = <expr>` adds `try_reset()`, which resets the counter
+/// like `reset()` but only up to the given number of times, after which it
+/// returns `ResetLimitExceeded` and leaves the counter alone. Plain `reset()`
+/// is unaffected.
+///
+/// Passing `prefix = "<str>"` adds `next_formatted()`, which returns the next
+/// value as a `String` with the prefix in front, e.g. `req-0`, `req-1`, and so
+/// on. It is only available with `std`.
+///
+/// Passing the `monotonic` flag guards against accidentally moving the counter
+/// backwards: in debug builds, `set()` (and so `reset_to()`) panics if the
+/// new value is less than the current one. `reset()` can still return to the
+/// start, and `restore()`, `scope()` guards and `with_counter()` can still
+/// put the counter back.
+///
+/// Passing the `track_extremes` flag additionally remembers the lowest and
+/// highest values the counter has held, available from `min()` and `max()`.
+///
+/// The counter can also be consumed as an iterator via `iter()`, which shares
+/// its state with `next()`.
+///
+/// The generated module is `pub` unless a visibility is given before the
+/// name, e.g. `generate_counter!(pub(crate) Counter, usize)`. Use
+/// `pub(self)` for a counter private to the current module. Attributes and
+/// doc comments placed before the name are forwarded to the generated module.
+/// Since counters are usually named like types, the module allows the
+/// `non_snake_case` lint, which can be turned back on by passing
+/// `#[warn(non_snake_case)]` or similar.
+///
 /// # Example
 ///
 /// ```rust
@@ -14,48 +181,545 @@
 ///   assert_eq!(Counter::next(), 1);
 ///   assert_eq!(Counter::next(), 2);
 /// 
+///   // Look at the next value without consuming it
+///   assert_eq!(Counter::peek(), 3);
+///   assert_eq!(Counter::next(), 3);
+/// 
 ///   Counter::reset();
 /// 
 ///   assert_eq!(Counter::next(), 0);
 /// }
 /// ```
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_counter!(Counter, usize, start = 1000);
+/// 
+/// fn main() {
+/// 
+///   assert_eq!(Counter::next(), 1000);
+///   assert_eq!(Counter::next(), 1001);
+/// 
+///   Counter::reset();
+/// 
+///   assert_eq!(Counter::next(), 1000);
+/// }
+/// ```
+///
+/// ```rust,compile_fail
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_counter!(#[deny(non_snake_case)] Counter, usize);
+/// 
+/// fn main() {}
+/// ```
+///
+/// ```rust,compile_fail
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// mod ids {
+///   generate_counter!(pub(self) Counter, usize);
+/// }
+/// 
+/// fn main() {
+///   ids::Counter::next();
+/// }
+/// ```
+///
+/// ```rust,compile_fail
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// use std::thread;
+/// 
+/// generate_counter!(Counter, usize);
+/// 
+/// fn main() {
+///   let guard = Counter::scope();
+///   thread::spawn(move || drop(guard));
+/// }
+/// ```
 #[macro_export]
 macro_rules! generate_counter {
-    ($name:ident, $type:ident) => {
+    (@parse $flavor:ident $(#[$attr:meta])* $name:ident, $($rest:tt)*) => {
+        $crate::generate_counter!(@parse $flavor $(#[$attr])* pub $name, $($rest)*);
+    };
 
-        /// Generated by the `simple-counter` crate.
+    (@parse $flavor:ident $(#[$attr:meta])* $vis:vis $name:ident, $type:ty) => {
+        $crate::generate_counter!(@counter $flavor $(#[$attr])* $vis $name, $type, []);
+    };
+
+    (@parse $flavor:ident $(#[$attr:meta])* $vis:vis $name:ident, $type:ty, $key:ident = $value:expr) => {
+        $crate::generate_counter!(@counter $flavor $(#[$attr])* $vis $name, $type, [($key = $value)]);
+    };
+
+    (@parse $flavor:ident $(#[$attr:meta])* $vis:vis $name:ident, $type:ty, $flag:ident) => {
+        $crate::generate_counter!(@counter $flavor $(#[$attr])* $vis $name, $type, [($flag)]);
+    };
+
+    (@parse $flavor:ident $(#[$attr:meta])* $vis:vis $name:ident, $type:ty, { $($key:ident $(: $value:expr)?),* $(,)? }) => {
+        $crate::generate_counter!(@counter $flavor $(#[$attr])* $vis $name, $type, [$(($key $(= $value)?))*]);
+    };
+
+    (@parse $flavor:ident $($input:tt)*) => {
+        compile_error!("expected a counter name and type, optionally followed by one option or a braced list of options");
+    };
+
+    (@counter $flavor:ident $(#[$attr:meta])* $vis:vis $name:ident, $type:ty, [$($option:tt)*]) => {
+
+        // Comes before the caller's attributes so they can override it.
         #[allow(non_snake_case)]
-        pub mod $name {
-            use std::cell::Cell;
+        $(#[$attr])*
+        /// Generated by the `simple-counter` crate.
+        $vis mod $name {
+            // Brings types and constants from the calling module into scope.
+            #[allow(unused_imports)]
+            use super::*;
+
+            use $crate::__private::{Counter, State};
+
+            // The counter's logic lives in the `Counter` trait, so `$type` is
+            // only required to be numeric here, and a type that isn't is
+            // reported once instead of by every function below.
+            enum Spec {}
+
+            impl Counter for Spec {
+                type Value = $type;
 
-            thread_local!(
-                static COUNTER: Cell<$type> = Cell::new(0);
-            );
+                fn with<R, F: FnOnce(&State<Self::Value>) -> R>(f: F) -> R {
+                    // Any use of the counter, even a read, registers it.
+                    Self::register();
+                    COUNTER.with(f)
+                }
 
+                $($crate::generate_counter!(@config $type, $option);)*
+            }
+
+            #[allow(dead_code)]
+            pub const MIN: $type = <Spec as Counter>::MIN;
+
+            #[allow(dead_code)]
+            pub const MAX: $type = <Spec as Counter>::MAX;
+
+            $($crate::generate_counter!(@option $flavor $type, $option);)*
+
+            $crate::generate_counter!(@storage $flavor COUNTER: State<$type> = State::new(<Spec as Counter>::START));
+            $crate::generate_counter!(@std $flavor $type);
+
+            #[allow(dead_code)]
+            pub fn on_next(callback: fn($type)) {
+                Spec::on_next(callback);
+            }
+
+            #[allow(dead_code)]
+            pub fn clear_on_next() {
+                Spec::clear_on_next();
+            }
+
+            #[allow(dead_code)]
+            pub fn total_issued() -> usize {
+                Spec::total_issued()
+            }
+
+            #[allow(dead_code)]
+            #[inline]
             pub fn next() -> $type {
-                COUNTER.with(|cell| {
-                    let n = cell.get();
-                    cell.set(n + 1);
-                    n
-                })
+                Spec::next()
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn add(by: $type) -> $type {
+                Spec::add(by)
+            }
+
+            #[allow(dead_code)]
+            pub fn skip(n: $type) {
+                Spec::skip(n);
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn next_into(out: &mut $type) {
+                *out = next();
+            }
+
+            #[allow(dead_code)]
+            pub fn next_value() -> $type {
+                Spec::next_value()
+            }
+
+            #[allow(dead_code)]
+            pub fn checked_next() -> Option<$type> {
+                Spec::checked_next()
+            }
+
+            #[allow(dead_code)]
+            pub fn wrapping_next() -> $type {
+                Spec::wrapping_next()
+            }
+
+            #[allow(dead_code)]
+            pub fn saturating_next() -> $type {
+                Spec::saturating_next()
+            }
+
+            #[allow(dead_code)]
+            pub fn prev() -> $type {
+                Spec::prev()
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn peek() -> $type {
+                Spec::peek()
             }
 
             #[allow(dead_code)]
+            pub fn get() -> $type {
+                Spec::get()
+            }
+
+            #[allow(dead_code)]
+            #[inline]
             pub fn set(n: $type) {
-                COUNTER.with(|cell| cell.set(n));
+                Spec::set(n);
+            }
+
+            #[allow(dead_code)]
+            pub fn snapshot() -> $type {
+                get()
+            }
+
+            #[allow(dead_code)]
+            pub fn restore(snapshot: $type) {
+                // Not `set()`, for the same reason as `ScopeGuard`.
+                Spec::overwrite(&snapshot);
+            }
+
+            /// Restores the counter to its value at creation when dropped.
+            ///
+            /// The guard can't be sent to another thread, which has its own
+            /// counter that the guard must not overwrite.
+            #[allow(dead_code)]
+            #[must_use = "the counter is restored as soon as the guard is dropped"]
+            #[derive(Debug)]
+            pub struct ScopeGuard {
+                saved: $type,
+                _not_send: $crate::__private::PhantomData<*const ()>,
+            }
+
+            impl Drop for ScopeGuard {
+                fn drop(&mut self) {
+                    // Not `set()`, which would panic here under `monotonic`.
+                    Spec::overwrite(&self.saved);
+                }
             }
 
             #[allow(dead_code)]
+            pub fn scope() -> ScopeGuard {
+                ScopeGuard {
+                    saved: get(),
+                    _not_send: $crate::__private::PhantomData,
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn with_counter<F, R>(start: $type, f: F) -> R
+            where
+                F: FnOnce() -> R,
+            {
+                let _guard = scope();
+                // Like the guard, bypasses the `monotonic` check in `set()`.
+                Spec::overwrite(&start);
+                f()
+            }
+
+            #[allow(dead_code)]
+            pub fn compare_and_set(current: $type, new: $type) -> bool {
+                Spec::compare_and_set(current, new)
+            }
+
+            #[allow(dead_code)]
+            pub fn update<F: FnOnce($type) -> $type>(f: F) -> $type {
+                Spec::update(f)
+            }
+
+            #[allow(dead_code)]
+            #[inline]
             pub fn reset() {
-                COUNTER.with(|cell| cell.set(0));
+                Spec::reset();
+            }
+
+            #[allow(dead_code)]
+            pub fn fetch_reset() -> $type {
+                Spec::fetch_reset()
+            }
+
+            #[allow(dead_code)]
+            pub fn reset_to(n: $type) {
+                set(n);
+            }
+
+            /// Iterator over the values handed out by `next()`.
+            ///
+            /// The iterator has no state of its own: it shares the counter
+            /// with direct calls to `next()`, so values it yields are consumed
+            /// for the rest of the thread as well.
+            #[allow(dead_code)]
+            #[derive(Clone, Copy, Debug)]
+            pub struct Iter;
+
+            impl Iterator for Iter {
+                type Item = $type;
+
+                fn next(&mut self) -> Option<$type> {
+                    Some(next())
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn iter() -> Iter {
+                Iter
             }
         }
-    }
+    };
+
+    (@storage thread_local $name:ident: $type:ty = $init:expr) => {
+        thread_local!(
+            static $name: $type = const { $init };
+        );
+    };
+
+    (@storage single_threaded $name:ident: $type:ty = $init:expr) => {
+        // Sound because the caller wrote `unsafe` in the invocation.
+        static $name: $crate::__private::SingleThreaded<$type> =
+            unsafe { $crate::__private::SingleThreaded::new($init) };
+    };
+
+    // Functions that need `std`, which are left out of `no_std` counters.
+    (@std thread_local $type:ty) => {
+        $crate::__counter_std!($type);
+    };
+
+    (@std single_threaded $type:ty) => {};
+
+    // Options that override items of the `Counter` impl.
+    (@config $type:ty, (start = $start:expr)) => {
+        const START: $type = $start;
+    };
+
+    (@config $type:ty, (step = $step:expr)) => {
+        const STEP: $type = $step;
+    };
+
+    (@config $type:ty, (modulo = $modulo:expr)) => {
+        fn reduce(n: $type) -> $type {
+            n % MODULO
+        }
+    };
+
+    (@config $type:ty, (wrap = $wrap:expr)) => {
+        $crate::generate_counter!(@config $type, (modulo = $wrap));
+    };
+
+    (@config $type:ty, (floor = $floor:expr)) => {
+        fn retreat(n: $type) -> $type {
+            // Compared this way round so a negative floor can't overflow.
+            match $crate::__private::Number::checked_add(FLOOR, Self::STEP) {
+                Some(limit) if n >= limit => n - Self::STEP,
+                _ => FLOOR,
+            }
+        }
+    };
+
+    (@config $type:ty, (registered)) => {
+        fn register() {
+            REGISTERED.call_once(|| $crate::registry::register(module_path!(), || get().to_string()));
+        }
+    };
+
+    (@config $type:ty, (monotonic)) => {
+        const MONOTONIC: bool = true;
+    };
+
+    (@config $type:ty, (track_extremes)) => {
+        fn record(n: $type) {
+            LOWEST.with(|cell| if n < cell.get() { cell.set(n) });
+            HIGHEST.with(|cell| if n > cell.get() { cell.set(n) });
+        }
+    };
+
+    (@config $type:ty, $option:tt) => {};
+
+    // Options that add items to the generated module.
+    (@option $flavor:ident $type:ty, (start = $start:expr)) => {};
+
+    (@option $flavor:ident $type:ty, (step = $step:expr)) => {};
+
+    (@option $flavor:ident $type:ty, (modulo = $modulo:expr)) => {
+        const MODULO: $type = $modulo;
+
+        const _: () = assert!(
+            MODULO != <$type as $crate::__private::Number>::ZERO,
+            "counter modulus must be nonzero",
+        );
+    };
+
+    (@option $flavor:ident $type:ty, (wrap = $wrap:expr)) => {
+        $crate::generate_counter!(@option $flavor $type, (modulo = $wrap));
+    };
+
+    (@option $flavor:ident $type:ty, (floor = $floor:expr)) => {
+        const FLOOR: $type = $floor;
+    };
+
+    (@option $flavor:ident $type:ty, (max = $max:expr)) => {
+        const CEILING: $type = $max;
+
+        #[allow(dead_code)]
+        pub fn try_next() -> Option<$type> {
+            Spec::try_next(CEILING)
+        }
+
+        #[allow(dead_code)]
+        pub fn remaining() -> $type {
+            Spec::remaining(CEILING)
+        }
+    };
+
+    (@option $flavor:ident $type:ty, (max_resets = $max_resets:expr)) => {
+        const MAX_RESETS: usize = $max_resets;
+
+        $crate::generate_counter!(@storage $flavor RESETS: $crate::__private::Cell<usize> = $crate::__private::Cell::new(0));
+
+        #[allow(dead_code)]
+        pub fn try_reset() -> Result<(), $crate::ResetLimitExceeded> {
+            RESETS.with(|cell| {
+                let resets = cell.get();
+                if resets < MAX_RESETS {
+                    cell.set(resets + 1);
+                    reset();
+                    Ok(())
+                } else {
+                    Err($crate::ResetLimitExceeded)
+                }
+            })
+        }
+    };
+
+    (@option $flavor:ident $type:ty, (prefix = $prefix:expr)) => {
+        const PREFIX: &str = $prefix;
+
+        #[allow(dead_code)]
+        pub fn next_formatted() -> String {
+            format!("{}{}", PREFIX, next())
+        }
+    };
+
+    (@option $flavor:ident $type:ty, (registered)) => {
+        static REGISTERED: $crate::__private::Once = $crate::__private::Once::new();
+    };
+
+    (@option $flavor:ident $type:ty, (monotonic)) => {};
+
+    (@option $flavor:ident $type:ty, (track_extremes)) => {
+        $crate::generate_counter!(@storage $flavor LOWEST: $crate::__private::Cell<$type> = $crate::__private::Cell::new(<Spec as Counter>::START));
+        $crate::generate_counter!(@storage $flavor HIGHEST: $crate::__private::Cell<$type> = $crate::__private::Cell::new(<Spec as Counter>::START));
+
+        #[allow(dead_code)]
+        pub fn min() -> $type {
+            LOWEST.with(|cell| cell.get())
+        }
+
+        #[allow(dead_code)]
+        pub fn max() -> $type {
+            HIGHEST.with(|cell| cell.get())
+        }
+    };
+
+    (@option $flavor:ident $type:ty, ($key:ident $($rest:tt)*)) => {
+        compile_error!(concat!("unknown counter option `", stringify!($key), "`"));
+    };
+
+    // Internal arms never fall through to the public one below, so a bug
+    // in the macro surfaces here instead of recursing.
+    (@ $($input:tt)*) => {
+        compile_error!("internal error in `generate_counter!`");
+    };
+
+    ($($input:tt)+) => {
+        $crate::generate_counter!(@parse thread_local $($input)+);
+    };
+}
+
+// Checked here rather than in the caller, whose own features say nothing
+// about whether this crate was built with `std`.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __counter_std {
+    ($type:ty) => {
+        #[allow(dead_code)]
+        pub fn next_n(count: usize) -> Vec<$type> {
+            Spec::next_n(count)
+        }
+
+        #[allow(dead_code)]
+        pub fn peek_n(count: usize) -> Vec<$type> {
+            Spec::peek_n(count)
+        }
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __counter_std {
+    ($type:ty) => {};
+}
+
+/// Generates several independent thread-local global counters at once.
+///
+/// Each `Name: type` pair expands to its own `generate_counter!` invocation,
+/// along with any attributes placed before it.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_counters! {
+///     Requests: usize,
+///     Responses: u64,
+/// }
+/// 
+/// fn main() {
+/// 
+///   assert_eq!(Requests::next(), 0);
+///   assert_eq!(Requests::next(), 1);
+///   assert_eq!(Responses::next(), 0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_counters {
+    ($($(#[$attr:meta])* $name:ident : $type:ty),* $(,)?) => {
+        $($crate::generate_counter!($(#[$attr])* $name, $type);)*
+    };
 }
 
 #[cfg(test)]
 mod tests {
 
+    type Id = u64;
+
+    const BASE: Id = 500;
+
     #[test]
     fn test_basic() {
         generate_counter!(Counter, i8);
@@ -81,4 +745,675 @@ mod tests {
         assert_eq!(101, Counter::next());
         assert_eq!(102, Counter::next());
     }
+
+    #[test]
+    fn test_peek() {
+        generate_counter!(Counter, u32);
+        assert_eq!(0, Counter::peek());
+        assert_eq!(0, Counter::peek());
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::peek());
+        assert_eq!(1, Counter::next());
+    }
+
+    #[test]
+    fn test_type_path() {
+        generate_counter!(Counter, ::std::os::raw::c_uint);
+        Counter::set(10);
+        assert_eq!(10, Counter::next());
+        assert_eq!(11, Counter::next());
+    }
+
+    #[test]
+    fn test_type_alias() {
+        generate_counter!(Counter, Id, start = BASE);
+        assert_eq!(500, Counter::next());
+        assert_eq!(501, Counter::next());
+        Counter::reset();
+        assert_eq!(BASE, Counter::next());
+    }
+
+    #[test]
+    fn test_start() {
+        generate_counter!(Counter, u64, start = 1000);
+        assert_eq!(1000, Counter::next());
+        assert_eq!(1001, Counter::next());
+        Counter::reset();
+        assert_eq!(1000, Counter::next());
+        Counter::set(5);
+        assert_eq!(5, Counter::next());
+        Counter::reset();
+        assert_eq!(1000, Counter::next());
+    }
+
+    #[test]
+    fn test_step() {
+        generate_counter!(Counter, usize, step = 4);
+        assert_eq!(0, Counter::next());
+        assert_eq!(4, Counter::next());
+        assert_eq!(8, Counter::next());
+        assert_eq!(12, Counter::next());
+        Counter::set(10);
+        assert_eq!(10, Counter::next());
+        assert_eq!(14, Counter::next());
+        assert_eq!(18, Counter::next());
+        Counter::reset();
+        assert_eq!(0, Counter::next());
+        assert_eq!(4, Counter::next());
+    }
+
+    #[test]
+    fn test_prev() {
+        generate_counter!(Counter, i32);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(1, Counter::prev());
+        assert_eq!(0, Counter::prev());
+        assert_eq!(-1, Counter::prev());
+        assert_eq!(-1, Counter::next());
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::peek());
+    }
+
+    #[test]
+    fn test_checked_next() {
+        generate_counter!(Counter, i8);
+        Counter::set(125);
+        assert_eq!(Some(125), Counter::checked_next());
+        assert_eq!(Some(126), Counter::checked_next());
+        assert_eq!(None, Counter::checked_next());
+        assert_eq!(None, Counter::checked_next());
+        assert_eq!(127, Counter::peek());
+    }
+
+    #[test]
+    fn test_wrapping_next() {
+        generate_counter!(Counter, u8);
+        for n in 0..=255 {
+            assert_eq!(n, Counter::wrapping_next());
+        }
+        assert_eq!(0, Counter::wrapping_next());
+        assert_eq!(1, Counter::next());
+    }
+
+    #[test]
+    fn test_modulo() {
+        generate_counter!(Counter, usize, modulo = 3);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::next());
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::next());
+        Counter::set(7);
+        assert_eq!(1, Counter::peek());
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::next());
+        assert_eq!(0, Counter::next());
+    }
+
+    #[test]
+    fn test_iter() {
+        generate_counter!(Counter, u16);
+        let taken = Counter::iter().take(5).collect::<Vec<_>>();
+        assert_eq!(vec![0, 1, 2, 3, 4], taken);
+        assert_eq!(5, Counter::next());
+        assert_eq!(Some(6), Counter::iter().next());
+    }
+
+    #[test]
+    fn test_add() {
+        generate_counter!(Counter, usize);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::add(10));
+        assert_eq!(11, Counter::next());
+        Counter::set(100);
+        assert_eq!(100, Counter::add(5));
+        assert_eq!(105, Counter::next());
+        Counter::reset();
+        assert_eq!(0, Counter::add(3));
+        assert_eq!(3, Counter::next());
+    }
+
+    #[test]
+    fn test_many() {
+        generate_counters! {
+            Requests: usize,
+            Responses: u64,
+            Errors: u32
+        }
+        assert_eq!(0, Requests::next());
+        assert_eq!(1, Requests::next());
+        assert_eq!(0, Responses::next());
+        Errors::set(10);
+        assert_eq!(2, Requests::next());
+        assert_eq!(1, Responses::next());
+        assert_eq!(10, Errors::next());
+    }
+
+    #[test]
+    fn test_visibility() {
+        mod outer {
+            generate_counter!(pub(crate) Crate, usize);
+
+            mod inner {
+                generate_counter!(pub(super) Parent, usize);
+            }
+
+            pub fn parent_next() -> usize {
+                inner::Parent::next()
+            }
+        }
+        assert_eq!(0, outer::Crate::next());
+        assert_eq!(0, outer::parent_next());
+        assert_eq!(1, outer::parent_next());
+        assert_eq!(1, outer::Crate::next());
+    }
+
+    #[test]
+    fn test_attributes() {
+        generate_counter!(
+            /// Only exists in test builds.
+            #[cfg(test)]
+            Counter, usize, start = 10
+        );
+        generate_counter!(#[cfg(not(test))] Counter, usize);
+        assert_eq!(10, Counter::next());
+    }
+
+    #[test]
+    fn test_get() {
+        generate_counter!(Counter, u32);
+        Counter::set(42);
+        assert_eq!(42, Counter::get());
+        assert_eq!(42, Counter::get());
+        Counter::add(8);
+        assert_eq!(50, Counter::get());
+    }
+
+    #[test]
+    fn test_get_modulo() {
+        generate_counter!(Counter, u32, modulo = 10);
+        Counter::set(42);
+        assert_eq!(42, Counter::get());
+        assert_eq!(2, Counter::peek());
+    }
+
+    #[test]
+    fn test_next_value() {
+        generate_counter!(Counter, usize);
+        assert_eq!(1, Counter::next_value());
+        assert_eq!(2, Counter::next_value());
+        assert_eq!(3, Counter::next_value());
+        assert_eq!(3, Counter::next());
+        assert_eq!(5, Counter::next_value());
+    }
+
+    #[test]
+    fn test_next_into() {
+        generate_counter!(Counter, u64, step = 3);
+        let mut out = 0;
+        let mut expected = Vec::new();
+        for _ in 0..4 {
+            Counter::next_into(&mut out);
+            expected.push(out);
+        }
+        Counter::reset();
+        assert_eq!(expected, (0..4).map(|_| Counter::next()).collect::<Vec<_>>());
+        assert_eq!(vec![0, 3, 6, 9], expected);
+    }
+
+    #[test]
+    fn test_saturating_next() {
+        generate_counter!(Counter, u8);
+        Counter::set(253);
+        assert_eq!(253, Counter::saturating_next());
+        assert_eq!(254, Counter::saturating_next());
+        assert_eq!(255, Counter::saturating_next());
+        assert_eq!(255, Counter::saturating_next());
+        assert_eq!(255, Counter::saturating_next());
+        assert_eq!(255, Counter::peek());
+    }
+
+    #[test]
+    fn test_update() {
+        generate_counter!(Counter, u64);
+        Counter::set(1);
+        assert_eq!(1, Counter::get());
+        assert_eq!(2, Counter::update(|n| n * 2));
+        assert_eq!(4, Counter::update(|n| n * 2));
+        assert_eq!(8, Counter::update(|n| n * 2));
+        assert_eq!(8, Counter::next());
+    }
+
+    #[test]
+    fn test_compare_and_set() {
+        generate_counter!(Counter, i16);
+        Counter::set(5);
+        assert!(!Counter::compare_and_set(4, 10));
+        assert_eq!(5, Counter::get());
+        assert!(Counter::compare_and_set(5, 10));
+        assert_eq!(10, Counter::get());
+        assert!(!Counter::compare_and_set(5, 20));
+        assert_eq!(10, Counter::next());
+    }
+
+    #[test]
+    fn test_reset_to() {
+        generate_counter!(Counter, usize, start = 100);
+        assert_eq!(100, Counter::next());
+        Counter::reset_to(5);
+        assert_eq!(5, Counter::next());
+        assert_eq!(6, Counter::next());
+        assert_eq!(7, Counter::next());
+        Counter::reset();
+        assert_eq!(100, Counter::next());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_next_n() {
+        generate_counter!(Counter, u8);
+        assert_eq!(vec![0, 1, 2], Counter::next_n(3));
+        assert_eq!(3, Counter::peek());
+        assert_eq!(Vec::<u8>::new(), Counter::next_n(0));
+        assert_eq!(3, Counter::next());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_next_n_step() {
+        generate_counter!(Counter, u8, step = 5);
+        assert_eq!(vec![0, 5, 10], Counter::next_n(3));
+        assert_eq!(15, Counter::next());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_peek_n() {
+        generate_counter!(Counter, u8);
+        assert_eq!(vec![0, 1, 2], Counter::peek_n(3));
+        assert_eq!(0, Counter::next());
+        assert_eq!(Counter::peek_n(4), Counter::next_n(4));
+    }
+
+    #[test]
+    fn test_track_extremes() {
+        generate_counter!(Counter, i32, track_extremes);
+        assert_eq!(0, Counter::min());
+        assert_eq!(0, Counter::max());
+        Counter::set(-5);
+        Counter::set(10);
+        Counter::set(0);
+        assert_eq!(-5, Counter::min());
+        assert_eq!(10, Counter::max());
+        Counter::set(-1);
+        Counter::prev();
+        Counter::prev();
+        Counter::prev();
+        Counter::prev();
+        Counter::prev();
+        assert_eq!(-6, Counter::min());
+        Counter::set(9);
+        Counter::next();
+        Counter::next();
+        assert_eq!(11, Counter::max());
+        Counter::reset();
+        assert_eq!(0, Counter::get());
+        assert_eq!(-6, Counter::min());
+        assert_eq!(11, Counter::max());
+    }
+
+    #[test]
+    fn test_skip() {
+        generate_counter!(Counter, u32);
+        Counter::skip(100);
+        assert_eq!(100, Counter::next());
+        Counter::skip(0);
+        assert_eq!(101, Counter::next());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        generate_counter!(Counter, usize);
+        Counter::next();
+        let snapshot = Counter::snapshot();
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::next());
+        assert_eq!(3, Counter::next());
+        Counter::restore(snapshot);
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::next());
+    }
+
+    #[test]
+    fn test_monotonic() {
+        generate_counter!(Counter, usize, monotonic);
+        assert_eq!(0, Counter::next());
+        Counter::set(10);
+        Counter::set(10);
+        assert_eq!(10, Counter::next());
+        Counter::reset();
+        assert_eq!(0, Counter::next());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "monotonic counter cannot be set backwards")]
+    fn test_monotonic_backwards() {
+        generate_counter!(Counter, usize, monotonic);
+        Counter::set(10);
+        Counter::set(5);
+    }
+
+    #[test]
+    fn test_monotonic_restore() {
+        generate_counter!(Counter, usize, monotonic);
+        Counter::set(5);
+        let snapshot = Counter::snapshot();
+        assert_eq!(5, Counter::next());
+        assert_eq!(6, Counter::next());
+        Counter::restore(snapshot);
+        assert_eq!(5, Counter::next());
+    }
+
+    #[test]
+    fn test_monotonic_scope() {
+        generate_counter!(Counter, usize, monotonic);
+        Counter::set(5);
+        {
+            let _guard = Counter::scope();
+            assert_eq!(5, Counter::next());
+            assert_eq!(6, Counter::next());
+        }
+        assert_eq!(5, Counter::next());
+    }
+
+    #[test]
+    fn test_backwards() {
+        generate_counter!(Counter, usize);
+        Counter::set(10);
+        Counter::set(5);
+        assert_eq!(5, Counter::next());
+    }
+
+    #[test]
+    fn test_float() {
+        generate_counter!(Clock, f64, step = 0.5);
+        assert_eq!(0.0, Clock::next());
+        assert_eq!(0.5, Clock::next());
+        assert_eq!(1.0, Clock::next());
+        assert_eq!(1.5, Clock::add(0.25));
+        assert_eq!(1.75, Clock::next());
+        Clock::reset();
+        assert_eq!(0.0, Clock::next());
+    }
+
+    #[test]
+    fn test_float_overflow() {
+        generate_counter!(Clock, f32, step = f32::MAX);
+        assert_eq!(Some(0.0), Clock::checked_next());
+        assert_eq!(None, Clock::checked_next());
+        assert_eq!(f32::MAX, Clock::saturating_next());
+        assert_eq!(f32::MAX, Clock::saturating_next());
+    }
+
+    #[test]
+    fn test_prefix() {
+        generate_counter!(Counter, usize, prefix = "req-");
+        assert_eq!("req-0", Counter::next_formatted());
+        assert_eq!("req-1", Counter::next_formatted());
+        assert_eq!(2, Counter::next());
+        assert_eq!("req-3", Counter::next_formatted());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_next() {
+        use std::cell::RefCell;
+
+        thread_local!(static SEEN: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) });
+
+        fn record(n: u32) {
+            SEEN.with(|seen| seen.borrow_mut().push(n));
+        }
+
+        generate_counter!(Counter, u32);
+        Counter::on_next(record);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::next());
+        SEEN.with(|seen| assert_eq!(vec![0, 1, 2], *seen.borrow()));
+
+        Counter::add(10);
+        assert_eq!(vec![13, 14], Counter::next_n(2));
+        SEEN.with(|seen| assert_eq!(vec![0, 1, 2, 13, 14], *seen.borrow()));
+
+        Counter::clear_on_next();
+        Counter::next();
+        SEEN.with(|seen| assert_eq!(5, seen.borrow().len()));
+    }
+
+    #[test]
+    fn test_scope() {
+        generate_counter!(Counter, usize);
+        assert_eq!(0, Counter::next());
+        {
+            let _guard = Counter::scope();
+            assert_eq!(1, Counter::next());
+            assert_eq!(2, Counter::next());
+            {
+                let _inner = Counter::scope();
+                assert_eq!(3, Counter::next());
+            }
+            assert_eq!(3, Counter::next());
+        }
+        assert_eq!(1, Counter::peek());
+        assert_eq!(1, Counter::next());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_with_counter() {
+        use std::panic;
+
+        generate_counter!(Counter, u16);
+        Counter::next();
+
+        let values = Counter::with_counter(500, || Counter::next_n(2));
+        assert_eq!(vec![500, 501], values);
+        assert_eq!(1, Counter::peek());
+
+        let result = panic::catch_unwind(|| {
+            Counter::with_counter(9, || {
+                assert_eq!(9, Counter::next());
+                panic!("counter-consuming code failed");
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(1, Counter::next());
+    }
+
+    #[test]
+    fn test_with_counter_monotonic() {
+        use std::panic;
+
+        generate_counter!(Counter, u16, monotonic);
+        Counter::set(100);
+
+        assert_eq!(5, Counter::with_counter(5, Counter::next));
+        assert_eq!(100, Counter::peek());
+
+        let result = panic::catch_unwind(|| {
+            Counter::with_counter(1, || {
+                Counter::next();
+                panic!("counter-consuming code failed");
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(100, Counter::next());
+    }
+
+    #[test]
+    fn test_max() {
+        generate_counter!(Counter, usize, max = 3);
+        assert_eq!(Some(0), Counter::try_next());
+        assert_eq!(Some(1), Counter::try_next());
+        assert_eq!(Some(2), Counter::try_next());
+        assert_eq!(None, Counter::try_next());
+        assert_eq!(None, Counter::try_next());
+        assert_eq!(3, Counter::peek());
+        Counter::reset();
+        assert_eq!(Some(0), Counter::try_next());
+    }
+
+    #[test]
+    fn test_fetch_reset() {
+        generate_counter!(Counter, u32);
+        Counter::add(5);
+        assert_eq!(5, Counter::fetch_reset());
+        assert_eq!(0, Counter::get());
+        assert_eq!(0, Counter::fetch_reset());
+    }
+
+    #[test]
+    fn test_max_resets() {
+        use ResetLimitExceeded;
+
+        generate_counter!(Counter, usize, max_resets = 2);
+        Counter::next();
+        assert_eq!(Ok(()), Counter::try_reset());
+        assert_eq!(0, Counter::next());
+        assert_eq!(Ok(()), Counter::try_reset());
+        assert_eq!(0, Counter::next());
+        assert_eq!(Err(ResetLimitExceeded), Counter::try_reset());
+        assert_eq!(1, Counter::next());
+        assert_eq!(Err(ResetLimitExceeded), Counter::try_reset());
+    }
+
+    #[test]
+    fn test_floor() {
+        generate_counter!(Counter, usize, floor = 0);
+        assert_eq!(0, Counter::prev());
+        assert_eq!(0, Counter::prev());
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(1, Counter::prev());
+        assert_eq!(0, Counter::prev());
+        assert_eq!(0, Counter::prev());
+    }
+
+    #[test]
+    fn test_floor_negative() {
+        generate_counter!(Counter, i8, floor = -100);
+        Counter::set(100);
+        assert_eq!(99, Counter::prev());
+        Counter::set(-99);
+        assert_eq!(-100, Counter::prev());
+        assert_eq!(-100, Counter::prev());
+    }
+
+    #[test]
+    fn test_floor_step() {
+        generate_counter!(Counter, u8, { floor: 250, step: 10 });
+        Counter::set(255);
+        assert_eq!(250, Counter::prev());
+    }
+
+    #[test]
+    fn test_remaining() {
+        generate_counter!(Counter, u32, max = 10);
+        assert_eq!(10, Counter::remaining());
+        Counter::try_next();
+        Counter::try_next();
+        Counter::try_next();
+        assert_eq!(7, Counter::remaining());
+        Counter::set(12);
+        assert_eq!(0, Counter::remaining());
+    }
+
+    #[test]
+    fn test_remaining_step() {
+        generate_counter!(Counter, u32, { step: 3, max: 10 });
+        assert_eq!(4, Counter::remaining());
+        assert_eq!(Some(0), Counter::try_next());
+        assert_eq!(3, Counter::remaining());
+        assert_eq!(Some(3), Counter::try_next());
+        assert_eq!(Some(6), Counter::try_next());
+        assert_eq!(1, Counter::remaining());
+        assert_eq!(Some(9), Counter::try_next());
+        assert_eq!(0, Counter::remaining());
+        assert_eq!(None, Counter::try_next());
+    }
+
+    #[test]
+    fn test_remaining_float() {
+        generate_counter!(Counter, f64, { step: 0.5, max: 1.2 });
+        assert_eq!(3.0, Counter::remaining());
+        Counter::set(0.2);
+        assert_eq!(2.0, Counter::remaining());
+    }
+
+    #[test]
+    fn test_max_near_type_max() {
+        generate_counter!(Counter, u8, { step: 2, max: 255 });
+        Counter::set(254);
+        assert_eq!(Some(254), Counter::try_next());
+        assert_eq!(None, Counter::try_next());
+        assert_eq!(255, Counter::peek());
+        assert_eq!(0, Counter::remaining());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_total_issued() {
+        generate_counter!(Counter, i32);
+        assert_eq!(0, Counter::total_issued());
+        Counter::next();
+        Counter::next();
+        Counter::set(-20);
+        Counter::next();
+        Counter::reset();
+        Counter::next();
+        Counter::next();
+        assert_eq!(2, Counter::peek());
+        assert_eq!(5, Counter::total_issued());
+        Counter::next_n(3);
+        assert_eq!(8, Counter::total_issued());
+    }
+
+    #[test]
+    fn test_type_bounds() {
+        generate_counter!(Counter, u8);
+        assert_eq!(255, Counter::MAX);
+        assert_eq!(0, Counter::MIN);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_combined_options() {
+        generate_counter!(Counter, usize, { start: 100, step: 2, wrap: 200 });
+        assert_eq!(vec![100, 102, 104], Counter::next_n(3));
+        Counter::set(196);
+        assert_eq!(vec![196, 198, 0, 2], Counter::next_n(4));
+        Counter::reset();
+        assert_eq!(100, Counter::next());
+    }
+
+    #[test]
+    fn test_combined_options_any_order() {
+        generate_counter!(Counter, u32, { monotonic, max: 20, step: 5, start: 10, });
+        assert_eq!(Some(10), Counter::try_next());
+        assert_eq!(Some(15), Counter::try_next());
+        assert_eq!(None, Counter::try_next());
+        assert_eq!(0, Counter::remaining());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_combined_options_defaults() {
+        generate_counter!(Counter, i8, { track_extremes });
+        assert_eq!(vec![0, 1, 2], Counter::next_n(3));
+        assert_eq!(3, Counter::max());
+    }
 }
@@ -0,0 +1,81 @@
+/// Generates a global counter for single-threaded `#![no_std]` environments.
+///
+/// `generate_counter!` relies on `thread_local!`, which is only available
+/// with `std`. This variant instead stores the counter in a plain `static`,
+/// and otherwise accepts the same arguments and generates the same functions,
+/// except that the name must be preceded by `unsafe`.
+///
+/// # Safety
+///
+/// The generated `static` is shared by every thread without any
+/// synchronization, yet its functions are safe to call. By writing `unsafe`,
+/// the caller promises that the counter is only used in an environment with
+/// a single thread of execution, such as a bare-metal program without
+/// threads, and never from interrupt or signal handlers that can preempt
+/// other uses of the counter. Use `generate_atomic_counter!` wherever that
+/// cannot be guaranteed.
+///
+/// # Example
+///
+/// ```rust
+/// #![no_std]
+/// 
+/// #[macro_use]
+/// extern crate simple_counter;
+/// # // Provides the panic handler when this crate is built without `std`.
+/// # extern crate std;
+/// 
+/// generate_counter_core!(unsafe Counter, usize);
+/// 
+/// fn main() {
+/// 
+///   assert_eq!(Counter::next(), 0);
+///   assert_eq!(Counter::next(), 1);
+/// 
+///   Counter::reset();
+/// 
+///   assert_eq!(Counter::next(), 0);
+/// }
+/// ```
+///
+/// ```rust,compile_fail
+/// #![no_std]
+/// 
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_counter_core!(Counter, usize);
+/// 
+/// fn main() {}
+/// ```
+#[macro_export]
+macro_rules! generate_counter_core {
+    ($(#[$attr:meta])* unsafe $($input:tt)*) => {
+        $crate::generate_counter!(@parse single_threaded $(#[$attr])* $($input)*);
+    };
+
+    ($($input:tt)*) => {
+        compile_error!("`generate_counter_core!` is unsafe to use: write `generate_counter_core!(unsafe Name, type)`");
+    };
+}
+
+/// Storage for `generate_counter_core!`, see its safety section.
+#[doc(hidden)]
+pub struct SingleThreaded<T>(T);
+
+unsafe impl<T: Send> Sync for SingleThreaded<T> {}
+
+impl<T> SingleThreaded<T> {
+    /// # Safety
+    ///
+    /// The storage must only ever be accessed from a single thread of
+    /// execution, as promised by the caller of `generate_counter_core!`.
+    pub const unsafe fn new(value: T) -> Self {
+        SingleThreaded(value)
+    }
+
+    /// Mirrors `LocalKey::with` so both storages share the same callers.
+    pub fn with<R, F: FnOnce(&T) -> R>(&'static self, f: F) -> R {
+        f(&self.0)
+    }
+}
@@ -0,0 +1,93 @@
+use core::ops::{Add, Sub};
+
+/// The numeric types a counter can be generated for, and the arithmetic the
+/// generated counters need beyond the standard operators.
+///
+/// The integer implementations defer to the inherent methods of the same
+/// name. Floating-point types have no overflow in the integer sense, so for
+/// them `checked_add` fails once the result is no longer finite,
+/// `saturating_add` stops at the largest finite value, and `wrapping_add` is
+/// ordinary addition.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "counter type must be numeric",
+    label = "`{Self}` is not a primitive integer or floating-point type",
+)]
+pub trait Number: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// How many steps of `rhs` it takes to cover `self`, for positive values.
+    fn div_ceil(self, rhs: Self) -> Self;
+}
+
+macro_rules! integer {
+    ($($type:ty)*) => {
+        $(
+            impl Number for $type {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MIN: Self = <$type>::MIN;
+                const MAX: Self = <$type>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$type>::checked_add(self, rhs)
+                }
+
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    <$type>::wrapping_add(self, rhs)
+                }
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$type>::saturating_add(self, rhs)
+                }
+
+                fn div_ceil(self, rhs: Self) -> Self {
+                    // The inherent `div_ceil` isn't stable for signed types.
+                    self / rhs + if self % rhs == 0 { 0 } else { 1 }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float {
+    ($($type:ty)*) => {
+        $(
+            impl Number for $type {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+                const MIN: Self = <$type>::MIN;
+                const MAX: Self = <$type>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    Some(self + rhs).filter(|n| n.is_finite())
+                }
+
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    (self + rhs).clamp(<$type>::MIN, <$type>::MAX)
+                }
+
+                fn div_ceil(self, rhs: Self) -> Self {
+                    // `ceil` needs `std`.
+                    let quotient = self / rhs;
+                    let whole = quotient - quotient % 1.0;
+                    if whole < quotient { whole + 1.0 } else { whole }
+                }
+            }
+        )*
+    };
+}
+
+integer!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+float!(f32 f64);
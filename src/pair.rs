@@ -0,0 +1,98 @@
+/// Generates a thread-local counter of `(major, minor)` pairs.
+///
+/// Each call to `next()` returns the current pair and advances the minor
+/// component. Once the minor component reaches `minor_wrap`, it rolls back
+/// over to `0` and the major component is incremented instead, so with
+/// `minor_wrap = 100` the counter goes `(0, 98), (0, 99), (1, 0)`. `reset()`
+/// zeroes both components.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// generate_pair_counter!(Version, usize, minor_wrap = 2);
+/// 
+/// fn main() {
+/// 
+///   assert_eq!(Version::next(), (0, 0));
+///   assert_eq!(Version::next(), (0, 1));
+///   assert_eq!(Version::next(), (1, 0));
+/// 
+///   Version::reset();
+/// 
+///   assert_eq!(Version::next(), (0, 0));
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_pair_counter {
+    ($name:ident, $type:ty, minor_wrap = $wrap:expr) => {
+
+        /// Generated by the `simple-counter` crate.
+        #[allow(non_snake_case)]
+        pub mod $name {
+            use $crate::__private::Cell;
+
+            const MINOR_WRAP: $type = $wrap;
+
+            const _: () = assert!(MINOR_WRAP != 0 as $type, "counter minor wrap must be nonzero");
+
+            thread_local!(
+                static COUNTER: Cell<($type, $type)> = const { Cell::new((0 as $type, 0 as $type)) };
+            );
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn next() -> ($type, $type) {
+                COUNTER.with(|cell| {
+                    let (major, minor) = cell.get();
+                    if minor + 1 as $type >= MINOR_WRAP {
+                        cell.set((major + 1 as $type, 0 as $type));
+                    } else {
+                        cell.set((major, minor + 1 as $type));
+                    }
+                    (major, minor)
+                })
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn peek() -> ($type, $type) {
+                COUNTER.with(|cell| cell.get())
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn reset() {
+                COUNTER.with(|cell| cell.set((0 as $type, 0 as $type)));
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_rollover() {
+        generate_pair_counter!(Counter, usize, minor_wrap = 100);
+        for minor in 0..100 {
+            assert_eq!((0, minor), Counter::next());
+        }
+        assert_eq!((1, 0), Counter::peek());
+        assert_eq!((1, 0), Counter::next());
+        assert_eq!((1, 1), Counter::next());
+    }
+
+    #[test]
+    fn test_reset() {
+        generate_pair_counter!(Counter, u8, minor_wrap = 3);
+        for _ in 0..7 {
+            Counter::next();
+        }
+        assert_eq!((2, 1), Counter::peek());
+        Counter::reset();
+        assert_eq!((0, 0), Counter::next());
+    }
+}
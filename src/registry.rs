@@ -0,0 +1,116 @@
+//! A process-wide registry of counters for diagnostics.
+//!
+//! Counters generated with `generate_registered_counter!` add themselves here
+//! the first time they are used, so every counter in use can be listed
+//! with `dump()`.
+
+use std::sync::Mutex;
+
+type Entry = (&'static str, fn() -> String);
+
+static REGISTRY: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+#[doc(hidden)]
+pub fn register(name: &'static str, read: fn() -> String) {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+        .push((name, read));
+}
+
+/// Returns the name and current value of every registered counter, in the
+/// order they were registered.
+///
+/// Names are the full module path of each counter, e.g. `my_crate::ids::Requests`.
+/// Since the counters are thread-local, the values are those seen by the
+/// calling thread.
+pub fn dump() -> Vec<(&'static str, String)> {
+    let entries = REGISTRY
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone();
+    entries
+        .into_iter()
+        .map(|(name, read)| (name, read()))
+        .collect()
+}
+
+/// Generates a thread-local global counter that is listed by
+/// `registry::dump()`.
+///
+/// Apart from registering itself the first time it is used, the
+/// counter behaves exactly like one from `generate_counter!`. Requires the
+/// `std` feature.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// use simple_counter::registry;
+/// 
+/// generate_registered_counter!(Requests, usize);
+/// 
+/// fn main() {
+/// 
+///   Requests::next();
+///   Requests::next();
+/// 
+///   assert_eq!(registry::dump(), vec![("rust_out::Requests", "2".to_string())]);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_registered_counter {
+    ($(#[$attr:meta])* $name:ident, $($rest:tt)*) => {
+        $crate::generate_registered_counter!($(#[$attr])* pub $name, $($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $name:ident, $type:ty) => {
+        $crate::generate_counter!(@counter thread_local $(#[$attr])* $vis $name, $type, [(registered)]);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::dump;
+
+    fn find(name: &str) -> Option<String> {
+        dump()
+            .into_iter()
+            .find(|&(registered, _)| registered == name)
+            .map(|(_, value)| value)
+    }
+
+    #[test]
+    fn test_dump() {
+        generate_registered_counter!(First, usize);
+        generate_registered_counter!(Second, i32);
+        assert_eq!(None, find("simple_counter::registry::tests::First"));
+
+        First::next();
+        First::next();
+        Second::set(-5);
+
+        assert_eq!(Some("2".to_string()), find("simple_counter::registry::tests::First"));
+        assert_eq!(Some("-5".to_string()), find("simple_counter::registry::tests::Second"));
+
+        Second::next();
+        assert_eq!(Some("-4".to_string()), find("simple_counter::registry::tests::Second"));
+
+        let names = dump()
+            .into_iter()
+            .filter(|&(name, _)| name.ends_with("::First"))
+            .count();
+        assert_eq!(1, names);
+    }
+
+    #[test]
+    fn test_dump_read_only() {
+        generate_registered_counter!(Read, u8);
+        assert_eq!(None, find("simple_counter::registry::tests::Read"));
+        assert_eq!(0, Read::peek());
+        assert_eq!(Some("0".to_string()), find("simple_counter::registry::tests::Read"));
+    }
+}
@@ -0,0 +1,23 @@
+use core::cell::Cell;
+
+/// Everything a generated counter keeps per thread.
+///
+/// Keeping it in a single thread-local means handing out a value, which
+/// also has to notify the callback and count the value, only takes one
+/// lookup.
+#[doc(hidden)]
+pub struct State<T> {
+    pub value: Cell<T>,
+    pub callback: Cell<Option<fn(T)>>,
+    pub total: Cell<usize>,
+}
+
+impl<T> State<T> {
+    pub const fn new(value: T) -> Self {
+        State {
+            value: Cell::new(value),
+            callback: Cell::new(None),
+            total: Cell::new(0),
+        }
+    }
+}
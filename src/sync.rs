@@ -0,0 +1,156 @@
+/// Generates a global counter shared across threads and protected by a
+/// `Mutex`.
+///
+/// This is an alternative to `generate_atomic_counter!` for types without a
+/// stable atomic counterpart, such as `u128`, the floating-point types, or a
+/// custom type that isn't even `Copy`. Any type implementing `Clone`,
+/// `Default`, `AddAssign` and `From<u8>` works: the counter starts at the
+/// default value, advances by adding `1`, and hands out clones of its value.
+///
+/// Every operation takes the lock, so unlike the atomic variant, threads
+/// hammering the same counter serialize on it instead of sharing a single
+/// hardware increment. Prefer `generate_atomic_counter!` for hot, heavily
+/// contended counters whenever the type allows it. Requires the `std`
+/// feature.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// use std::thread;
+/// 
+/// generate_sync_counter!(Counter, u128);
+/// 
+/// fn main() {
+/// 
+///   let handle = thread::spawn(|| Counter::next());
+///   let mine = Counter::next();
+///   let theirs = handle.join().unwrap();
+/// 
+///   assert_ne!(mine, theirs);
+///   assert_eq!(Counter::peek(), 2);
+/// 
+///   Counter::reset();
+/// 
+///   assert_eq!(Counter::next(), 0);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_sync_counter {
+    ($name:ident, $type:ty) => {
+
+        /// Generated by the `simple-counter` crate.
+        #[allow(non_snake_case)]
+        pub mod $name {
+            // Brings custom counter types from the calling module into scope.
+            #[allow(unused_imports)]
+            use super::*;
+
+            use $crate::__private::{LazyLock, Mutex, MutexGuard};
+
+            // `Default::default()` isn't `const`, so the value is created on
+            // first use.
+            static COUNTER: LazyLock<Mutex<$type>> = LazyLock::new(|| Mutex::new(Default::default()));
+
+            // A panic while holding the lock, e.g. from an overflowing `+=`,
+            // shouldn't make the counter unusable for every other thread.
+            fn lock() -> MutexGuard<'static, $type> {
+                COUNTER.lock().unwrap_or_else(|error| error.into_inner())
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn next() -> $type {
+                let mut counter = lock();
+                let n = counter.clone();
+                *counter += <$type>::from(1u8);
+                n
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn peek() -> $type {
+                lock().clone()
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn set(n: $type) {
+                *lock() = n;
+            }
+
+            #[allow(dead_code)]
+            #[inline]
+            pub fn reset() {
+                set(Default::default());
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::ops::AddAssign;
+    use std::thread;
+
+    #[test]
+    fn test_basic() {
+        generate_sync_counter!(Counter, i128);
+        assert_eq!(0, Counter::next());
+        assert_eq!(1, Counter::next());
+        assert_eq!(2, Counter::peek());
+        Counter::set(-7);
+        assert_eq!(-7, Counter::next());
+        Counter::reset();
+        assert_eq!(0, Counter::next());
+    }
+
+    // Deliberately not `Copy`.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Version(Vec<u32>);
+
+    impl From<u8> for Version {
+        fn from(n: u8) -> Self {
+            Version(vec![n as u32])
+        }
+    }
+
+    impl AddAssign for Version {
+        fn add_assign(&mut self, other: Version) {
+            match self.0.last_mut() {
+                Some(last) => *last += other.0[0],
+                None => self.0 = other.0,
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_type() {
+        generate_sync_counter!(Counter, Version);
+        assert_eq!(Version(vec![]), Counter::next());
+        assert_eq!(Version(vec![1]), Counter::next());
+        Counter::set(Version(vec![2, 7]));
+        assert_eq!(Version(vec![2, 7]), Counter::next());
+        assert_eq!(Version(vec![2, 8]), Counter::peek());
+        Counter::reset();
+        assert_eq!(Version(vec![]), Counter::peek());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_poisoned() {
+        generate_sync_counter!(Counter, u8);
+        Counter::set(255);
+
+        // Overflows while holding the lock, poisoning it.
+        assert!(thread::spawn(Counter::next).join().is_err());
+
+        assert_eq!(255, Counter::peek());
+        Counter::set(3);
+        assert_eq!(3, Counter::next());
+        assert_eq!(4, Counter::peek());
+    }
+}
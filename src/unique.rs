@@ -0,0 +1,129 @@
+/// Generates a counter that is unique across threads without atomic
+/// increments.
+///
+/// Each thread keeps its own thread-local count, like `generate_counter!`,
+/// but `next()` packs it together with a stable per-thread identifier so that
+/// no two threads ever hand out the same value. The only synchronization is a
+/// single atomic increment the first time a thread uses the counter.
+///
+/// # Layout
+///
+/// The top quarter of the bits hold the thread identifier and the rest hold
+/// the thread-local count. For a `u64` counter, that is 16 bits for the
+/// thread and 48 bits for the count:
+///
+/// ```text
+/// 63          48 47                                            0
+/// +-------------+-----------------------------------------------+
+/// |   thread    |                     count                     |
+/// +-------------+-----------------------------------------------+
+/// ```
+///
+/// So a `u64` counter supports up to 65,536 threads over the life of the
+/// program, each handing out up to 2^48 values. Rather than silently
+/// colliding, `next()` panics once either limit is exceeded. Only unsigned
+/// integer types are supported.
+///
+/// # Example
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate simple_counter;
+/// 
+/// use std::thread;
+/// 
+/// generate_unique_counter!(Counter, u64);
+/// 
+/// fn main() {
+/// 
+///   let mine = Counter::next();
+///   let theirs = thread::spawn(|| Counter::next()).join().unwrap();
+/// 
+///   assert_ne!(mine, theirs);
+///   assert_eq!(Counter::next(), mine + 1);
+/// }
+/// ```
+#[macro_export]
+macro_rules! generate_unique_counter {
+    ($name:ident, $type:ty) => {
+
+        /// Generated by the `simple-counter` crate.
+        #[allow(non_snake_case)]
+        pub mod $name {
+            use $crate::__private::Cell;
+            use $crate::__private::atomic::{AtomicUsize, Ordering};
+
+            const THREAD_BITS: u32 = <$type>::BITS / 4;
+            const COUNT_BITS: u32 = <$type>::BITS - THREAD_BITS;
+
+            static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+            thread_local!(
+                static THREAD: $type = {
+                    let id = THREADS.fetch_add(1, Ordering::Relaxed);
+                    assert!(id < 1 << THREAD_BITS, "too many threads for unique counter");
+                    (id as $type) << COUNT_BITS
+                };
+
+                static COUNTER: Cell<$type> = const { Cell::new(0) };
+            );
+
+            #[allow(dead_code)]
+            pub fn next() -> $type {
+                let n = COUNTER.with(|cell| {
+                    let n = cell.get();
+                    assert!(n < 1 << COUNT_BITS, "unique counter exhausted for this thread");
+                    cell.set(n + 1);
+                    n
+                });
+                THREAD.with(|thread| thread | n)
+            }
+
+            #[allow(dead_code)]
+            pub fn peek() -> $type {
+                let n = COUNTER.with(|cell| cell.get());
+                THREAD.with(|thread| thread | n)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::HashSet;
+    use std::thread;
+
+    #[test]
+    fn test_basic() {
+        generate_unique_counter!(Counter, u64);
+        let first = Counter::next();
+        assert_eq!(first + 1, Counter::peek());
+        assert_eq!(first + 1, Counter::next());
+        assert_eq!(first + 2, Counter::next());
+    }
+
+    #[test]
+    fn test_thread_bits() {
+        generate_unique_counter!(Counter, u64);
+
+        let handles = (0..8)
+            .map(|_| thread::spawn(|| {
+                (0..3).map(|_| Counter::next()).collect::<Vec<_>>()
+            }))
+            .collect::<Vec<_>>();
+
+        let mut threads = HashSet::new();
+        for handle in handles {
+            let ids = handle.join().unwrap();
+            let thread = ids[0] >> 48;
+            assert!(threads.insert(thread));
+            for (count, n) in ids.into_iter().enumerate() {
+                assert_eq!(thread, n >> 48);
+                assert_eq!(count as u64, n & 0xFFFF_FFFF_FFFF);
+            }
+        }
+
+        assert_eq!(8, threads.len());
+    }
+}
@@ -0,0 +1,35 @@
+#![deny(warnings)]
+
+#[macro_use]
+extern crate simple_counter;
+
+// None of these are used, which would normally trip `dead_code`.
+mod unused {
+    generate_counter!(pub(crate) Counter, usize);
+    generate_counter!(pub(crate) Start, usize, start = 5);
+    generate_counter!(pub(crate) Extremes, i32, track_extremes);
+    generate_counter_core!(unsafe pub(crate) Core, u8);
+    generate_counters! {
+        First: u32,
+        Second: u64,
+    }
+    generate_atomic_counter!(Atomic, usize);
+    generate_atomic_counter!(Relaxed, usize, ordering = Relaxed);
+    generate_unique_counter!(Unique, u64);
+    generate_pair_counter!(Pair, usize, minor_wrap = 100);
+    #[cfg(feature = "std")]
+    generate_sync_counter!(Locked, u128);
+    #[cfg(feature = "std")]
+    generate_registered_counter!(pub(crate) Registered, usize);
+    generate_counter_type!(pub(crate) Instance, usize);
+    generate_const_counter!(pub(crate) Const, usize);
+}
+
+generate_counter!(snake_case, usize);
+
+#[test]
+fn test_lints() {
+    assert_eq!(0, snake_case::next());
+    generate_counter!(Local, usize);
+    assert_eq!(0, Local::next());
+}
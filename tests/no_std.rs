@@ -0,0 +1,34 @@
+#![no_std]
+
+#[macro_use]
+extern crate simple_counter;
+
+generate_counter_core!(unsafe Counter, usize);
+generate_counter_core!(unsafe Offset, i32, start = -10);
+
+#[test]
+fn test_core() {
+    assert_eq!(0, Counter::next());
+    assert_eq!(1, Counter::next());
+    assert_eq!(2, Counter::peek());
+    Counter::reset();
+    assert_eq!(0, Counter::next());
+}
+
+#[test]
+fn test_core_start() {
+    assert_eq!(-10, Offset::next());
+    assert_eq!(-9, Offset::next());
+    Offset::reset();
+    assert_eq!(-10, Offset::next());
+}
+
+#[test]
+fn test_core_track_extremes() {
+    generate_counter_core!(unsafe Extremes, i32, track_extremes);
+    Extremes::set(-5);
+    Extremes::set(10);
+    Extremes::set(0);
+    assert_eq!(-5, Extremes::min());
+    assert_eq!(10, Extremes::max());
+}
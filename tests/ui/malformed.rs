@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate simple_counter;
+
+generate_counter!(Counter, usize, start = 1, step = 2);
+
+fn main() {}
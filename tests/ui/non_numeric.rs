@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate simple_counter;
+
+generate_counter!(Counter, String);
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate simple_counter;
+
+generate_counter!(Counter, usize, strat = 5);
+generate_counter!(Braced, usize, { start: 1, monotnic });
+
+fn main() {}